@@ -0,0 +1,148 @@
+//! Presenting several shared-file segments as one continuous stream.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Deref;
+
+use crate::{calc_pos, SharedFileSlice};
+
+/// A reader that concatenates an ordered list of [`SharedFileSlice`]
+/// segments into a single logical byte stream.
+///
+/// Reads roll transparently from the end of one segment into the start
+/// of the next, and `SeekFrom::End` resolves against the sum of the
+/// segments' lengths. This makes it possible to read across
+/// non-contiguous regions of one file — or across several files — as a
+/// single stream without copying the segments together first.
+///
+pub struct GatheringReader<F> {
+    segments: Vec<SharedFileSlice<F>>,
+    lengths: Vec<u64>,
+    total_len: u64,
+    pos: u64,
+}
+
+impl<F> GatheringReader<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    /// Build a `GatheringReader` over `segments`, presented in order.
+    pub fn new(segments: Vec<SharedFileSlice<F>>) -> Self {
+        let lengths: Vec<u64> = segments.iter().map(SharedFileSlice::len).collect();
+        let total_len = lengths.iter().sum();
+        Self {
+            segments,
+            lengths,
+            total_len,
+            pos: 0,
+        }
+    }
+
+    /// Find the segment index and intra-segment offset for `pos`.
+    fn locate(&self, pos: u64) -> Option<(usize, u64)> {
+        let mut start = 0u64;
+        for (i, len) in self.lengths.iter().enumerate() {
+            if pos < start + len {
+                return Some((i, pos - start));
+            }
+            start += len;
+        }
+        None
+    }
+}
+
+impl<F> Clone for GatheringReader<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            segments: self.segments.clone(),
+            lengths: self.lengths.clone(),
+            total_len: self.total_len,
+            // To be consistent with `SharedFile::clone`, don't copy the
+            // read position.
+            pos: 0,
+        }
+    }
+}
+
+impl<F> Read for GatheringReader<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some((idx, intra)) = self.locate(self.pos) else {
+            return Ok(0);
+        };
+        let segment = &mut self.segments[idx];
+        segment.seek(SeekFrom::Start(intra))?;
+        let bytes_read = segment.read(buf)?;
+        self.pos += bytes_read as u64;
+        Ok(bytes_read)
+    }
+}
+
+impl<F> Seek for GatheringReader<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(spos) => spos,
+            SeekFrom::End(epos) => calc_pos(self.total_len, epos)?,
+            SeekFrom::Current(cpos) => calc_pos(self.pos, cpos)?,
+        };
+        self.pos = new_pos;
+        Ok(new_pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SharedFile;
+    use std::io::Write;
+    use tempfile::tempfile;
+
+    #[test]
+    fn reads_across_segments() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"0123456789").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let segments = vec![shared.slice(0, 3), shared.slice(6, 10)];
+        let mut gathered = GatheringReader::new(segments);
+
+        let mut s = String::new();
+        gathered.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "012" /* gap skipped */.to_owned() + "6789");
+    }
+
+    #[test]
+    fn seek_end_is_summed_length() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"0123456789").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let segments = vec![shared.slice(0, 3), shared.slice(6, 10)];
+        let mut gathered = GatheringReader::new(segments);
+
+        assert_eq!(gathered.seek(SeekFrom::End(0)).unwrap(), 7);
+    }
+
+    #[test]
+    fn seek_start_lands_in_second_segment() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"0123456789").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let segments = vec![shared.slice(0, 3), shared.slice(6, 10)];
+        let mut gathered = GatheringReader::new(segments);
+
+        gathered.seek(SeekFrom::Start(4)).unwrap();
+        let mut buf = [0u8; 1];
+        gathered.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"7");
+    }
+}
@@ -1,15 +1,45 @@
 use std::convert::TryInto;
 use std::fs::File;
-use std::io::{self, Read, Seek, SeekFrom};
+use std::io::{self, IoSliceMut, Read, Seek, SeekFrom};
 use std::ops::Deref;
-#[cfg(target_family = "unix")]
-use std::os::unix::fs::FileExt;
 use std::sync::Arc;
 
-fn u64_from(x: usize) -> u64 {
+mod gather;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod posread;
+mod slice;
+#[cfg(target_family = "unix")]
+mod sparse;
+mod write;
+pub use gather::GatheringReader;
+#[cfg(feature = "mmap")]
+pub use mmap::MappedFile;
+use posread::PosRead;
+pub use slice::SharedFileSlice;
+pub use write::{SharedArcFileWrite, SharedFileWrite, SharedRefFileWrite};
+
+pub(crate) fn u64_from(x: usize) -> u64 {
     x.try_into().expect("usize should fit in u64")
 }
 
+/// Add a signed seek offset to an unsigned position.
+///
+/// Used by the `Seek` impls of [`SharedFile`], [`SharedFileWrite`],
+/// [`SharedFileSlice`] and [`GatheringReader`] to turn a base position plus an `i64` offset into a `u64` result,
+/// reporting overflow as an `io::Error` rather than panicking or wrapping.
+pub(crate) fn calc_pos(pos: u64, offset: i64) -> io::Result<u64> {
+    // Convert to i64; add the seek offset; convert back to u64.
+    // Any failure along the way will be carried along as None,
+    // and converted to io::Error at the end.
+    let pos: Option<u64> = pos
+        .try_into()
+        .ok()
+        .and_then(|p: i64| p.checked_add(offset))
+        .and_then(|p| p.try_into().ok());
+    pos.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek overflow"))
+}
+
 /// A cheaply clone-able File wrapper.
 ///
 /// All clones of `SharedFile` share the same underlying `File`.
@@ -19,6 +49,10 @@ fn u64_from(x: usize) -> u64 {
 pub struct SharedFile<F> {
     file: F,
     pos: u64,
+    // Cached file length, used to resolve `SeekFrom::End` without a
+    // `metadata()` syscall. `None` unless the caller opted in via
+    // `with_len`/`with_len_owned`.
+    length: Option<u64>,
 }
 
 /// A `SharedFile` that uses an `Arc<File>` for file access.
@@ -50,8 +84,35 @@ where
             // We could, but it would be more confusing than
             // helpful.
             pos: 0,
+            length: None,
         }
     }
+
+    /// Create a `SharedFile` with a cached file `length`, used to resolve
+    /// `SeekFrom::End` without a `metadata()` syscall.
+    ///
+    /// The cached length is authoritative for end-relative seeks: it is
+    /// neither checked against nor updated from the inner file, so callers
+    /// reading an append-only or truncating file choose the semantics
+    /// explicitly by picking the length they pass in. Reads themselves
+    /// remain unconstrained by `length` and still return 0 past EOF.
+    pub fn with_len(file: F, length: u64) -> Self {
+        Self {
+            file,
+            pos: 0,
+            length: Some(length),
+        }
+    }
+
+    /// Return a [`SharedFileSlice`] bounded to the fixed `[start, end)`
+    /// byte range of the underlying file.
+    ///
+    /// Reads through the returned slice never return data past `end`,
+    /// and `SeekFrom::End` resolves relative to `end` rather than the
+    /// underlying file's length.
+    pub fn slice(&self, start: u64, end: u64) -> SharedFileSlice<F> {
+        SharedFileSlice::new(self.file.clone(), start, end)
+    }
 }
 
 impl SharedArcFile {
@@ -62,6 +123,16 @@ impl SharedArcFile {
             // We could, but it would be more confusing than
             // helpful.
             pos: 0,
+            length: None,
+        }
+    }
+
+    /// Like [`SharedFile::with_len`], taking ownership of `file`.
+    pub fn with_len_owned(file: File, length: u64) -> Self {
+        Self {
+            file: Arc::new(file),
+            pos: 0,
+            length: Some(length),
         }
     }
 }
@@ -75,6 +146,9 @@ where
             file: Clone::clone(&self.file),
             // To be consistent with `new`, don't copy the file position.
             pos: 0,
+            // The cached length describes the file, not the clone's
+            // position, so it carries over.
+            length: self.length,
         }
     }
 }
@@ -84,7 +158,13 @@ where
     F: Deref<Target = File>,
 {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let bytes_read = self.file.read_at(buf, self.pos)?;
+        let bytes_read = self.file.pos_read(buf, self.pos)?;
+        self.pos += u64_from(bytes_read);
+        Ok(bytes_read)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let bytes_read = self.file.pos_read_vectored(bufs, self.pos)?;
         self.pos += u64_from(bytes_read);
         Ok(bytes_read)
     }
@@ -95,19 +175,6 @@ where
     F: Clone + Deref<Target = File>,
 {
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
-        // Add i64 offset to a u64 position.
-        fn calc_pos(pos: u64, offset: i64) -> io::Result<u64> {
-            // Convert to i64; add the seek offset; convert back to u64.
-            // Any failure along the way will be carried along as None,
-            // and converted to io::Error at the end.
-            let pos: Option<u64> = pos
-                .try_into()
-                .ok()
-                .and_then(|p: i64| p.checked_add(offset))
-                .and_then(|p| p.try_into().ok());
-            pos.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek overflow"))
-        }
-
         match pos {
             SeekFrom::Start(spos) => {
                 // According to the docs for Seek::seek,
@@ -117,10 +184,19 @@ where
                 Ok(spos)
             }
             SeekFrom::End(epos) => {
-                let file_len = self.file.metadata()?.len();
-                calc_pos(file_len, epos)
+                let file_len = match self.length {
+                    Some(length) => length,
+                    None => self.file.metadata()?.len(),
+                };
+                let new_pos = calc_pos(file_len, epos)?;
+                self.pos = new_pos;
+                Ok(new_pos)
+            }
+            SeekFrom::Current(cpos) => {
+                let new_pos = calc_pos(self.pos, cpos)?;
+                self.pos = new_pos;
+                Ok(new_pos)
             }
-            SeekFrom::Current(cpos) => calc_pos(self.pos, cpos),
         }
     }
 }
@@ -160,4 +236,49 @@ mod tests {
         f1.read_to_string(&mut s1).unwrap();
         f2.read_to_string(&mut s2).unwrap();
     }
+
+    #[test]
+    fn vectored_read() {
+        let buf = "hello world".as_bytes();
+        let mut file = tempfile().unwrap();
+        file.write_all(buf).unwrap();
+
+        let mut f1 = SharedFile::new(&file);
+        let mut hello = [0u8; 5];
+        let mut world = [0u8; 5];
+        let mut bufs = [
+            io::IoSliceMut::new(&mut hello),
+            io::IoSliceMut::new(&mut world),
+        ];
+        let n = f1.read_vectored(&mut bufs).unwrap();
+        assert_eq!(n, 10);
+        assert_eq!(&hello, b"hello");
+        assert_eq!(&world, b" worl");
+    }
+
+    #[test]
+    fn cached_len_seek_end() {
+        let buf = "hello world".as_bytes();
+        let mut file = tempfile().unwrap();
+        file.write_all(buf).unwrap();
+
+        // Lie about the length; `with_len` trusts the caller instead of
+        // calling `metadata()`.
+        let mut f1 = SharedFile::with_len(&file, 5);
+        assert_eq!(f1.seek(SeekFrom::End(0)).unwrap(), 5);
+    }
+
+    #[test]
+    fn seek_end_repositions_cursor_for_read() {
+        let buf = "hello world".as_bytes();
+        let mut file = tempfile().unwrap();
+        file.write_all(buf).unwrap();
+
+        let mut f1 = SharedFile::with_len(&file, 11);
+        f1.seek(SeekFrom::End(-5)).unwrap();
+
+        let mut s = String::new();
+        f1.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "world");
+    }
 }
@@ -0,0 +1,158 @@
+//! Zero-copy reads via a shared memory mapping, gated behind the `mmap`
+//! feature.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use memmap2::{Advice, Mmap, MmapOptions};
+
+use crate::SharedFile;
+
+/// A `SharedFile`-like reader served from a memory mapping instead of
+/// per-call positioned reads.
+///
+/// The mapping is created once and shared across clones behind an `Arc`,
+/// so cloning stays cheap even though the mapping itself is expensive to
+/// set up. Reads copy out of the mapping directly; [`MappedFile::as_slice`]
+/// hands out a borrowed `&[u8]` for callers that want true zero-copy
+/// access instead of going through `Read`.
+pub struct MappedFile {
+    mmap: Arc<Mmap>,
+    pos: usize,
+}
+
+impl<F> SharedFile<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    /// Map the underlying file into memory for zero-copy reads.
+    ///
+    /// The returned [`MappedFile`] is independent of this `SharedFile`'s
+    /// position, and can be cheaply cloned to share the same mapping.
+    pub fn mem_map(&self) -> io::Result<MappedFile> {
+        // Safety: the caller must not modify the file out from under the
+        // mapping (e.g. truncating it) for as long as the mapping lives;
+        // this is the same caveat `memmap2` documents for `Mmap::map`.
+        let mmap = unsafe { Mmap::map(&*self.file)? };
+        Ok(MappedFile {
+            mmap: Arc::new(mmap),
+            pos: 0,
+        })
+    }
+
+    /// Map a `[start, start + len)` byte range of the underlying file into
+    /// memory for zero-copy reads, rather than the whole file.
+    ///
+    /// Has the same safety caveat as [`SharedFile::mem_map`].
+    pub fn mem_map_range(&self, start: u64, len: u64) -> io::Result<MappedFile> {
+        // Safety: see `mem_map`.
+        let mmap = unsafe {
+            MmapOptions::new()
+                .offset(start)
+                .len(len as usize)
+                .map(&*self.file)?
+        };
+        Ok(MappedFile {
+            mmap: Arc::new(mmap),
+            pos: 0,
+        })
+    }
+}
+
+impl Clone for MappedFile {
+    fn clone(&self) -> Self {
+        Self {
+            mmap: Arc::clone(&self.mmap),
+            // To be consistent with `SharedFile::clone`, don't copy the
+            // read position.
+            pos: 0,
+        }
+    }
+}
+
+impl Read for MappedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = &self.mmap[self.pos..];
+        let n = std::cmp::min(buf.len(), remaining.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl MappedFile {
+    /// Borrow the full mapped contents directly, with no copy.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Advise the kernel that reads of this mapping will be mostly
+    /// sequential, to tune readahead for large scans.
+    pub fn advise_sequential(&self) -> io::Result<()> {
+        self.mmap.advise(Advice::Sequential)
+    }
+
+    /// Advise the kernel that reads of this mapping will be in random
+    /// order, disabling readahead.
+    pub fn advise_random(&self) -> io::Result<()> {
+        self.mmap.advise(Advice::Random)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SharedFile;
+    use std::io::{Read, Write};
+    use tempfile::tempfile;
+
+    #[test]
+    fn mapped_read() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"hello world").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let mut mapped = shared.mem_map().unwrap();
+
+        let mut s = String::new();
+        mapped.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "hello world");
+    }
+
+    #[test]
+    fn as_slice_is_zero_copy() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"hello world").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let mapped = shared.mem_map().unwrap();
+        assert_eq!(mapped.as_slice(), b"hello world");
+    }
+
+    #[test]
+    fn mapped_range_read() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"hello world").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let mapped = shared.mem_map_range(6, 5).unwrap();
+        assert_eq!(mapped.as_slice(), b"world");
+    }
+
+    #[test]
+    fn clone_has_independent_position() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"hello world").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let mut m1 = shared.mem_map().unwrap();
+        let mut buf = [0u8; 5];
+        m1.read_exact(&mut buf).unwrap();
+
+        let mut m2 = m1.clone();
+        let mut buf2 = [0u8; 5];
+        m2.read_exact(&mut buf2).unwrap();
+        assert_eq!(&buf2, b"hello");
+    }
+}
@@ -0,0 +1,107 @@
+//! Positioned I/O that dispatches to the right platform primitive, so the
+//! rest of the crate can stay OS-agnostic.
+//!
+//! Unix has `read_at`/`write_at` on `std::os::unix::fs::FileExt`; Windows
+//! has `seek_read`/`seek_write` on `std::os::windows::fs::FileExt`. These
+//! traits paper over that difference for any `Deref<Target = File>`.
+
+use std::fs::File;
+use std::io::{self, IoSliceMut};
+use std::ops::Deref;
+#[cfg(target_family = "unix")]
+use std::os::unix::fs::FileExt;
+#[cfg(target_family = "windows")]
+use std::os::windows::fs::FileExt;
+
+/// A positioned read at an explicit offset, independent of any shared
+/// `pos` this crate tracks.
+///
+/// On Unix this is `pread`, which also doesn't touch the underlying
+/// file's own cursor, so concurrent calls on different clones never
+/// race with each other. On Windows it dispatches to `seek_read`, which
+/// *does* move the shared `File` handle's cursor internally even though
+/// it takes an explicit offset. Each read still uses its explicit offset,
+/// and nothing in this crate reads that cursor, so the result does not
+/// depend on other calls made through clones of the same handle.
+pub(crate) trait PosRead {
+    fn pos_read(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+
+    /// Scatter-read into `bufs` starting at `offset`, in a single syscall
+    /// where the platform supports it. Where it doesn't, this falls back
+    /// to filling the slices one at a time with [`PosRead::pos_read`] at
+    /// increasing offsets, stopping at the first short read.
+    fn pos_read_vectored(&self, bufs: &mut [IoSliceMut<'_>], offset: u64) -> io::Result<usize> {
+        let mut total = 0usize;
+        let mut pos = offset;
+        for buf in bufs.iter_mut() {
+            let n = self.pos_read(buf, pos)?;
+            total += n;
+            pos += n as u64;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(total)
+    }
+}
+
+impl<T> PosRead for T
+where
+    T: Deref<Target = File>,
+{
+    #[cfg(target_family = "unix")]
+    fn pos_read(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.read_at(buf, offset)
+    }
+
+    #[cfg(target_family = "windows")]
+    fn pos_read(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.seek_read(buf, offset)
+    }
+
+    // On Unix this is a single `preadv` call; Windows has no positioned
+    // scatter read, so it relies on the trait's sequential fallback above.
+    #[cfg(target_family = "unix")]
+    fn pos_read_vectored(&self, bufs: &mut [IoSliceMut<'_>], offset: u64) -> io::Result<usize> {
+        use std::os::unix::io::AsRawFd;
+
+        // `IoSliceMut` is guaranteed to have the same memory layout as
+        // `iovec` on Unix, so it can be passed to `preadv` directly.
+        let fd = self.as_raw_fd();
+        let iov = bufs.as_mut_ptr().cast::<libc::iovec>();
+        let n = unsafe { libc::preadv(fd, iov, bufs.len() as libc::c_int, offset as libc::off_t) };
+        if n < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(n as usize)
+        }
+    }
+}
+
+/// A positioned write at an explicit offset, independent of any shared
+/// `pos` this crate tracks.
+///
+/// As with [`PosRead`], Unix's `pwrite` doesn't touch the underlying
+/// file's own cursor, so concurrent calls on different clones never
+/// race. Windows' `seek_write` does move the shared `File` handle's
+/// cursor internally despite taking an explicit offset; each write still
+/// lands at its explicit offset, and nothing in this crate reads that
+/// cursor.
+pub(crate) trait PosWrite {
+    fn pos_write(&self, buf: &[u8], offset: u64) -> io::Result<usize>;
+}
+
+impl<T> PosWrite for T
+where
+    T: Deref<Target = File>,
+{
+    #[cfg(target_family = "unix")]
+    fn pos_write(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        self.write_at(buf, offset)
+    }
+
+    #[cfg(target_family = "windows")]
+    fn pos_write(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        self.seek_write(buf, offset)
+    }
+}
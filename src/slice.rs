@@ -0,0 +1,179 @@
+//! A bounded, cheaply clone-able view over a fixed `[start, end)` byte range.
+
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom};
+use std::ops::Deref;
+
+use crate::posread::PosRead;
+use crate::{calc_pos, u64_from};
+
+/// A cheaply clone-able view over a fixed `[start, end)` range of a `File`.
+///
+/// Unlike [`SharedFile`](crate::SharedFile), reads through a `SharedFileSlice`
+/// never return bytes outside of `[start, end)`: `SeekFrom::Start(0)` maps to
+/// `start`, and `SeekFrom::End` resolves relative to `end` rather than the
+/// underlying file's length. This makes it possible to hand independent,
+/// non-overlapping sub-regions of one file (archive members, row groups,
+/// blob chunks) to separate consumers from a single cheap clone, each with
+/// its own position within the window.
+///
+pub struct SharedFileSlice<F> {
+    file: F,
+    start: u64,
+    end: u64,
+    pos: u64,
+}
+
+impl<F> SharedFileSlice<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    /// Create a new `SharedFileSlice` bounded to `[start, end)`.
+    ///
+    /// Panics if `start > end`.
+    pub(crate) fn new(file: F, start: u64, end: u64) -> Self {
+        assert!(start <= end, "slice start must not exceed end");
+        Self {
+            file,
+            start,
+            end,
+            // Position at the start of the window, same as `SharedFile::new`
+            // not inheriting a previous file position.
+            pos: start,
+        }
+    }
+}
+
+impl<F> SharedFileSlice<F> {
+    /// The number of bytes in this slice's `[start, end)` window.
+    pub(crate) fn len(&self) -> u64 {
+        self.end - self.start
+    }
+}
+
+impl<F> Clone for SharedFileSlice<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            file: Clone::clone(&self.file),
+            start: self.start,
+            end: self.end,
+            // To be consistent with `new`, don't copy the file position.
+            pos: self.start,
+        }
+    }
+}
+
+impl<F> io::Read for SharedFileSlice<F>
+where
+    F: Deref<Target = File>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.end.saturating_sub(self.pos);
+        let len = std::cmp::min(u64_from(buf.len()), remaining) as usize;
+        let bytes_read = self.file.pos_read(&mut buf[..len], self.pos)?;
+        self.pos += u64_from(bytes_read);
+        Ok(bytes_read)
+    }
+}
+
+impl<F> Seek for SharedFileSlice<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            // `SeekFrom::Start(0)` maps to the start of the window, not
+            // the start of the underlying file.
+            SeekFrom::Start(spos) => self.start.checked_add(spos).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "seek overflow")
+            })?,
+            // `SeekFrom::End` resolves relative to the slice's `end`,
+            // not the underlying file's length.
+            SeekFrom::End(epos) => calc_pos(self.end, epos)?,
+            SeekFrom::Current(cpos) => calc_pos(self.pos, cpos)?,
+        };
+        if new_pos < self.start {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            ));
+        }
+        self.pos = new_pos;
+        Ok(new_pos - self.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SharedFile;
+    use std::io::{Read, Seek, SeekFrom, Write};
+    use tempfile::tempfile;
+
+    #[test]
+    fn bounded_read() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"0123456789").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let mut slice = shared.slice(3, 7);
+
+        let mut s = String::new();
+        slice.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "3456");
+    }
+
+    #[test]
+    fn seek_within_window() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"0123456789").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let mut slice = shared.slice(3, 7);
+
+        assert_eq!(slice.seek(SeekFrom::Start(0)).unwrap(), 0);
+        assert_eq!(slice.seek(SeekFrom::End(0)).unwrap(), 4);
+
+        let mut buf = [0u8; 1];
+        slice.seek(SeekFrom::Start(1)).unwrap();
+        slice.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"4");
+    }
+
+    #[test]
+    fn seek_before_window_start_errors() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"0123456789").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let mut slice = shared.slice(3, 7);
+
+        assert!(slice.seek(SeekFrom::Current(-1)).is_err());
+        assert!(slice.seek(SeekFrom::End(-10)).is_err());
+
+        // The failed seeks must not have moved `pos` outside the window.
+        let mut buf = [0u8; 1];
+        slice.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"3");
+    }
+
+    #[test]
+    fn clone_has_independent_position() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"0123456789").unwrap();
+
+        let shared = SharedFile::new(&file);
+        let mut s1 = shared.slice(3, 7);
+        s1.seek(SeekFrom::Start(2)).unwrap();
+        let mut s2 = s1.clone();
+
+        let mut buf1 = [0u8; 1];
+        let mut buf2 = [0u8; 1];
+        s1.read_exact(&mut buf1).unwrap();
+        s2.read_exact(&mut buf2).unwrap();
+        assert_eq!(&buf1, b"5");
+        assert_eq!(&buf2, b"3");
+    }
+}
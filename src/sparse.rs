@@ -0,0 +1,84 @@
+//! Sparse-file hole navigation via `SEEK_DATA`/`SEEK_HOLE`.
+//!
+//! Unix-only: there's no portable equivalent, and Windows has its own
+//! sparse-file query APIs that aren't a good fit for a `Seek`-shaped call.
+
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::os::unix::io::AsRawFd;
+
+use crate::SharedFile;
+
+impl<F> SharedFile<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    /// Move `pos` to the offset of the next byte of data at or after
+    /// `offset`, skipping any hole in between.
+    ///
+    /// Returns an error (`ENXIO`) if `offset` is at or past the end of
+    /// the last data region in the file.
+    pub fn seek_data(&mut self, offset: u64) -> io::Result<u64> {
+        self.lseek_whence(offset, libc::SEEK_DATA)
+    }
+
+    /// Move `pos` to the start of the next hole at or after `offset`.
+    ///
+    /// The implicit hole at end-of-file is always reported, so for any
+    /// `offset` before the end of the file this finds a hole. Like
+    /// [`SharedFile::seek_data`], it returns an error (`ENXIO`) if
+    /// `offset` is at or past the end of the file.
+    pub fn seek_hole(&mut self, offset: u64) -> io::Result<u64> {
+        self.lseek_whence(offset, libc::SEEK_HOLE)
+    }
+
+    fn lseek_whence(&mut self, offset: u64, whence: libc::c_int) -> io::Result<u64> {
+        let fd = self.file.as_raw_fd();
+        // Safety: `fd` is a valid, open file descriptor for the lifetime
+        // of this call, borrowed from `self.file`.
+        let result = unsafe { libc::lseek(fd, offset as libc::off_t, whence) };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.pos = result as u64;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SharedFile;
+    use std::io::{Seek, Write};
+    use tempfile::tempfile;
+
+    #[test]
+    fn seek_hole_at_eof() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"hello").unwrap();
+
+        let mut shared = SharedFile::new(&file);
+        // The implicit hole at EOF is always reported.
+        let hole = shared.seek_hole(0).unwrap();
+        assert_eq!(hole, 5);
+    }
+
+    #[test]
+    fn seek_data_past_end_is_error() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"hello").unwrap();
+
+        let mut shared = SharedFile::new(&file);
+        assert!(shared.seek_data(10).is_err());
+    }
+
+    #[test]
+    fn seek_data_updates_pos() {
+        let mut file = tempfile().unwrap();
+        file.write_all(b"hello").unwrap();
+
+        let mut shared = SharedFile::new(&file);
+        shared.seek_data(2).unwrap();
+        assert_eq!(shared.stream_position().unwrap(), 2);
+    }
+}
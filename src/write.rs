@@ -0,0 +1,173 @@
+//! Positioned-write support: the write-side analogue of [`SharedFile`](crate::SharedFile).
+
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::posread::PosWrite;
+use crate::{calc_pos, u64_from};
+
+/// A cheaply clone-able `File` wrapper that writes using positioned writes.
+///
+/// All clones of `SharedFileWrite` share the same underlying `File`.
+/// Each instance performs `Write` and `Seek` operations independently,
+/// maintaining its own seek position, so several clones can write to
+/// disjoint regions of the same file concurrently without contending
+/// on a shared seek cursor.
+///
+pub struct SharedFileWrite<F> {
+    file: F,
+    pos: u64,
+}
+
+/// A `SharedFileWrite` that uses an `Arc<File>` for file access.
+///
+/// Choose this type if you want automatic management of the lifetime
+/// of the underlying `File`, or if the lifetime paramater of
+/// [`SharedRefFileWrite`] is troublesome.
+///
+pub type SharedArcFileWrite = SharedFileWrite<Arc<File>>;
+
+/// A `SharedFileWrite` that uses a `&File` for file access.
+///
+/// Choose this type if you want the cheapest, fastest code. It will
+/// mean convincing the compiler that the underlying `File` will outlive
+/// all the `SharedRefFileWrite` instances.
+///
+/// If that seems tricky, use [`SharedArcFileWrite`] instead.
+///
+pub type SharedRefFileWrite<'a> = SharedFileWrite<&'a File>;
+
+impl<F> SharedFileWrite<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    pub fn new(file: F) -> Self {
+        Self {
+            file,
+            // We don't inherit the previous file position.
+            // We could, but it would be more confusing than
+            // helpful.
+            pos: 0,
+        }
+    }
+}
+
+impl SharedArcFileWrite {
+    pub fn new_owned(file: File) -> Self {
+        Self {
+            file: Arc::new(file),
+            // We don't inherit the previous file position.
+            // We could, but it would be more confusing than
+            // helpful.
+            pos: 0,
+        }
+    }
+}
+
+impl<F> Clone for SharedFileWrite<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            file: Clone::clone(&self.file),
+            // To be consistent with `new`, don't copy the file position.
+            pos: 0,
+        }
+    }
+}
+
+impl<F> Write for SharedFileWrite<F>
+where
+    F: Deref<Target = File>,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes_written = self.file.pos_write(buf, self.pos)?;
+        self.pos += u64_from(bytes_written);
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Positioned writes go straight to the file; there's no
+        // userspace buffer to flush.
+        Ok(())
+    }
+}
+
+impl<F> Seek for SharedFileWrite<F>
+where
+    F: Clone + Deref<Target = File>,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match pos {
+            SeekFrom::Start(spos) => {
+                // According to the docs for Seek::seek,
+                // "A seek beyond the end of a stream is allowed, but
+                // behavior is defined by the implementation."
+                self.pos = spos;
+                Ok(spos)
+            }
+            SeekFrom::End(epos) => {
+                let file_len = self.file.metadata()?.len();
+                let new_pos = calc_pos(file_len, epos)?;
+                self.pos = new_pos;
+                Ok(new_pos)
+            }
+            SeekFrom::Current(cpos) => {
+                let new_pos = calc_pos(self.pos, cpos)?;
+                self.pos = new_pos;
+                Ok(new_pos)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::tempfile;
+
+    #[test]
+    fn ref_write() {
+        let file = tempfile().unwrap();
+        let mut f1 = SharedFileWrite::new(&file);
+        let mut f2 = f1.clone();
+
+        f1.write_all(b"hello").unwrap();
+        f2.seek(SeekFrom::Start(5)).unwrap();
+        f2.write_all(b" world").unwrap();
+
+        let mut buf = Vec::new();
+        (&file).read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"hello world");
+    }
+
+    #[test]
+    fn relative_seek_repositions_cursor() {
+        let file = tempfile().unwrap();
+        let mut f1 = SharedFileWrite::new(&file);
+
+        f1.write_all(b"AAAA").unwrap();
+        assert_eq!(f1.seek(SeekFrom::Current(4)).unwrap(), 8);
+        assert_eq!(f1.stream_position().unwrap(), 8);
+        f1.write_all(b"BB").unwrap();
+
+        let mut buf = Vec::new();
+        (&file).read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"AAAA\0\0\0\0BB");
+    }
+
+    #[test]
+    fn arc_write() {
+        let file = tempfile().unwrap();
+        let mut f1 = SharedArcFileWrite::new_owned(file);
+        let mut f2 = f1.clone();
+
+        f1.write_all(b"hello").unwrap();
+        f2.seek(SeekFrom::Start(5)).unwrap();
+        f2.write_all(b" world").unwrap();
+    }
+}